@@ -1,11 +1,15 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
 use tokio::fs;
 use hex::encode as hex_encode;
+use rustls::client::danger::{ServerCertVerified, ServerCertVerifier, HandshakeSignatureValid};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
 
 use crate::NVDARemoteError;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FingerprintCache {
     fingerprints: HashMap<String, String>,  // host -> fingerprint (hex string)
 }
@@ -46,16 +50,83 @@ impl FingerprintCache {
     // Get the fingerprint for a specific host
     pub fn get_fingerprint(&self, host: &str) -> Option<Vec<u8>> {
         let fingerprint_hex = self.fingerprints.get(host)?;
-        let fingerprint = hex::decode(fingerprint_hex).unwrap();  // Convert back to bytes
-        Some(fingerprint)
+        // A corrupted or hand-edited cache entry should make this host look
+        // unpinned rather than panic the whole handshake.
+        hex::decode(fingerprint_hex).ok()
     }
 
-    pub fn to_cert_store(&self) -> rustls::RootCertStore {
-        let mut store = rustls::RootCertStore::empty();
-        for fingerprint in self.fingerprints.values() {
-            let fingerprint = hex::decode(fingerprint).unwrap();
-            store.add(fingerprint.into()).unwrap();
+}
+
+/// Trust-on-first-use certificate verifier, modeled on the SSH `known_hosts`
+/// workflow NVDA Remote itself uses: the first certificate seen for a host is
+/// pinned, and every later connection to that host must present the exact
+/// same certificate.
+#[derive(Debug)]
+pub struct FingerprintVerifier {
+    host: String,
+    cache: Arc<Mutex<FingerprintCache>>,
+}
+
+impl FingerprintVerifier {
+    pub fn new(host: String, cache: Arc<Mutex<FingerprintCache>>) -> Self {
+        Self { host, cache }
+    }
+}
+
+impl ServerCertVerifier for FingerprintVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let digest = Sha256::digest(end_entity.as_ref()).to_vec();
+        let mut cache = self.cache.lock().unwrap();
+
+        match cache.get_fingerprint(&self.host) {
+            Some(stored) if stored == digest => Ok(ServerCertVerified::assertion()),
+            Some(_) => Err(rustls::Error::General(format!(
+                "certificate fingerprint for {} changed since it was first pinned",
+                self.host
+            ))),
+            None => {
+                cache.add_fingerprint(self.host.clone(), digest);
+                Ok(ServerCertVerified::assertion())
+            }
         }
-        store
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        crate::signature_verification_algorithms().supported_schemes()
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            crate::signature_verification_algorithms(),
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            crate::signature_verification_algorithms(),
+        )
     }
 }
@@ -0,0 +1,65 @@
+use std::io::Cursor;
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls_pemfile::{certs, private_key};
+use sha2::{Digest, Sha256};
+
+use crate::NVDARemoteError;
+
+/// A client certificate chain and private key, PEM-encoded, used for mutual
+/// TLS when connecting to relays that require client authentication.
+#[derive(Clone)]
+pub struct ClientIdentity {
+    cert_chain_pem: Vec<u8>,
+    key_pem: Vec<u8>,
+}
+
+impl ClientIdentity {
+    pub fn new(cert_chain_pem: Vec<u8>, key_pem: Vec<u8>) -> Self {
+        Self {
+            cert_chain_pem,
+            key_pem,
+        }
+    }
+
+    pub(crate) fn load(
+        &self,
+    ) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), NVDARemoteError> {
+        let cert_chain = certs(&mut Cursor::new(&self.cert_chain_pem))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| NVDARemoteError::TlsSetupError(e.to_string()))?;
+
+        let key = private_key(&mut Cursor::new(&self.key_pem))
+            .map_err(|e| NVDARemoteError::TlsSetupError(e.to_string()))?
+            .ok_or_else(|| {
+                NVDARemoteError::TlsSetupError("no private key found in PEM data".to_string())
+            })?;
+
+        Ok((cert_chain, key))
+    }
+}
+
+/// Subject/issuer/validity and fingerprint of a peer certificate, so an
+/// application can display "connected to `<CN>`, fingerprint `<hex>`" and
+/// drive a TOFU prompt.
+#[derive(Debug, Clone)]
+pub struct PeerCertificateInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub fingerprint: String,
+}
+
+pub(crate) fn parse_peer_certificate(der: &[u8]) -> Option<PeerCertificateInfo> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    let validity = cert.validity();
+
+    Some(PeerCertificateInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_before: validity.not_before.to_string(),
+        not_after: validity.not_after.to_string(),
+        fingerprint: hex::encode(Sha256::digest(der)),
+    })
+}
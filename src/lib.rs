@@ -1,55 +1,37 @@
-use rustls::{client::danger::{DangerousClientConfigBuilder, ServerCertVerifier}, ConfigBuilder, RootCertStore};
+use rustls::client::danger::DangerousClientConfigBuilder;
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
     net::TcpStream,
 };
 use serde_json::json;
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
-#[derive(Debug)]
-struct NoCertificateVerification;
+mod fingerprint;
+mod identity;
+mod session;
+mod speech;
+mod verifier;
+pub use fingerprint::{FingerprintCache, FingerprintVerifier};
+pub use identity::{ClientIdentity, PeerCertificateInfo};
+use identity::parse_peer_certificate;
+pub use session::ConnectOptions;
+pub use speech::SpeechSequence;
+pub use verifier::{lookup_tlsa_records, AllowAllVerifier, CertVerifier, DaneVerifier, TlsaRecord};
 
-impl ServerCertVerifier for NoCertificateVerification {
-    fn verify_server_cert(
-            &self,
-            end_entity: &rustls::pki_types::CertificateDer<'_>,
-            intermediates: &[rustls::pki_types::CertificateDer<'_>],
-            server_name: &rustls::pki_types::ServerName<'_>,
-            ocsp_response: &[u8],
-            now: rustls::pki_types::UnixTime,
-        ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
-            Ok(rustls::client::danger::ServerCertVerified::assertion())
-    }
-
-    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
-        vec![]
-    }
-
-    fn verify_tls12_signature(
-            &self,
-            message: &[u8],
-            cert: &rustls::pki_types::CertificateDer<'_>,
-            dss: &rustls::DigitallySignedStruct,
-        ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-            Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
-    
-        
-    }
-
-    fn verify_tls13_signature(
-            &self,
-            message: &[u8],
-            cert: &rustls::pki_types::CertificateDer<'_>,
-            dss: &rustls::DigitallySignedStruct,
-        ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
-            Ok(rustls::client::danger::HandshakeSignatureValid::assertion())
-    }
+/// The signature-verification algorithm set backing every [`CertVerifier`]
+/// that pins certificates instead of walking a CA chain (fingerprint, DANE).
+/// Pinning the certificate bytes alone only proves the peer *sent* a
+/// previously-seen certificate; checking the handshake signature against it
+/// is what proves the peer actually holds the matching private key.
+pub(crate) fn signature_verification_algorithms() -> &'static rustls::crypto::WebPkiSupportedAlgorithms
+{
+    static ALGORITHMS: std::sync::OnceLock<rustls::crypto::WebPkiSupportedAlgorithms> =
+        std::sync::OnceLock::new();
+    ALGORITHMS.get_or_init(|| rustls::crypto::ring::default_provider().signature_verification_algorithms)
 }
 
-
-
-
 #[derive(Error, Debug)]
 pub enum NVDARemoteError {
     #[error("IO error: {0}")]
@@ -58,6 +40,10 @@ pub enum NVDARemoteError {
     TlsError(#[from] tokio_rustls::rustls::Error),
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("DNS error: {0}")]
+    DnsError(String),
+    #[error("TLS setup error: {0}")]
+    TlsSetupError(String),
 }
 
 #[derive(Debug, Clone)]
@@ -80,11 +66,31 @@ pub enum EventType {
     Motd(String),
     ChannelJoined(i32),
     ChannelLeft,
-    ChannelMessage(String, i32),
     ClientJoined(i32, String),
     ClientLeft(i32),
     Beep(i32, i32, i32, i32),
+    /// A master sent a key press/release: (vk_code, scan_code, extended, pressed).
+    Key(i32, i32, bool, bool),
+    /// Speech to voice: (sequence, origin client id).
+    Speak(Vec<SpeechSequence>, i32),
+    CancelSpeech,
+    PauseSpeech(bool),
+    /// Braille display update: (cells, origin client id).
+    Braille(Vec<u8>, i32),
+    /// A slave announced its braille display: (display name, number of cells).
+    SetBrailleInfo(String, i32),
+    SendSAS,
+    Clipboard(String),
+    NvdaNotConnected,
     Invalid(String),
+    /// [`NVDARemote::run`] is dialing the server.
+    Connecting,
+    /// The connection is up and joined.
+    Connected,
+    /// The connection dropped and a reconnect is being scheduled.
+    Reconnecting,
+    /// The connection dropped; a reconnect is about to be scheduled.
+    Disconnected,
 }
 
 pub struct NVDARemote {
@@ -92,10 +98,24 @@ pub struct NVDARemote {
     pub port: u16,
     pub channel: String,
     pub connection_type: String,
-    soc: tokio_rustls::client::TlsStream<TcpStream>,
+    soc: BufReader<tokio_rustls::client::TlsStream<TcpStream>>,
+    /// Bytes of the current line read so far. Kept on `self` rather than as a
+    /// local in [`Self::update`]: `read_until` moves bytes out of `soc`'s
+    /// internal buffer as soon as they arrive, well before a full line is
+    /// found, so if the caller (`session::run`'s keepalive `timeout`) cancels
+    /// `update()` mid-read, a local accumulator would be dropped along with
+    /// whatever partial line it held. Keeping it here lets the next call pick
+    /// up where the cancelled one left off instead of losing those bytes.
+    line_buf: Vec<u8>,
     pressed_keys: HashSet<(i32, i32, bool)>,
     uid: i32,
     event_callback: Option<Box<dyn Fn(EventType) + Send>>,
+    /// Subject/issuer/validity/fingerprint of the server's certificate, if it
+    /// could be parsed.
+    pub peer_certificate: Option<PeerCertificateInfo>,
+    /// The parameters this connection was dialed with, kept around so
+    /// [`NVDARemote::run`] can redial after a drop.
+    connect_options: ConnectOptions,
 }
 
 impl NVDARemote {
@@ -104,16 +124,50 @@ impl NVDARemote {
         key: &str,
         connection_type: ConnectionType,
         port: u16,
+        verifier: CertVerifier,
+        client_identity: Option<ClientIdentity>,
     ) -> Result<Self, NVDARemoteError> {
+        let connect_options = ConnectOptions {
+            host: host.to_string(),
+            key: key.to_string(),
+            connection_type: connection_type.clone(),
+            port,
+            verifier: verifier.clone(),
+            client_identity: client_identity.clone(),
+        };
+
         let addr = format!("{}:{}", host, port);
         let stream = TcpStream::connect(addr).await?;
 
-        // Create the TLS connector, bypassing certificate validation
-        let config = std::sync::Arc::new(rustls::ClientConfig::builder().dangerous().with_custom_certificate_verifier(std::sync::Arc::new(NoCertificateVerification)).with_no_client_auth()
-        );
-        let tls_connector = tokio_rustls::TlsConnector::from(config);
+        let config_builder = rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(verifier.into_rustls_verifier(host));
+
+        let config = match &client_identity {
+            Some(identity) => {
+                let (cert_chain, key) = identity.load()?;
+                config_builder
+                    .with_client_auth_cert(cert_chain, key)
+                    .map_err(|e| NVDARemoteError::TlsSetupError(e.to_string()))?
+            }
+            None => config_builder.with_no_client_auth(),
+        };
+
+        let tls_connector = tokio_rustls::TlsConnector::from(std::sync::Arc::new(config));
         let domain = rustls::pki_types::ServerName::try_from(host.to_string()).unwrap();
-        let soc = tls_connector.connect(domain, stream).await?;  // Here is the error!
+        let soc = tls_connector.connect(domain, stream).await?;
+
+        let peer_certificate = soc
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| parse_peer_certificate(cert.as_ref()));
+
+        // Wrapped once here and kept for the lifetime of the connection:
+        // recreating a `BufReader` on every `update()` call would discard
+        // whatever it had already buffered past the current line.
+        let soc = BufReader::new(soc);
 
         Ok(Self {
             host: host.to_string(),
@@ -121,32 +175,145 @@ impl NVDARemote {
             channel: key.to_string(),
             connection_type: connection_type.to_string(),
             soc,
+            line_buf: Vec::new(),
             pressed_keys: HashSet::new(),
             uid: 0,
             event_callback: None,
+            peer_certificate,
+            connect_options,
         })
     }
-    
-    pub async fn join(&mut self) {
-        self.send(json!({"type": "protocol_version", "version": 2})).await;
-        self.send(json!({"type": "join", "channel": self.channel, "connection_type": self.connection_type})).await;
+
+    /// Like [`NVDARemote::new`] with [`CertVerifier::Fingerprint`], except it
+    /// also persists `cache` to `cache_path` after a successful handshake so
+    /// the pin survives process restarts.
+    pub async fn new_with_cache(
+        host: &str,
+        key: &str,
+        connection_type: ConnectionType,
+        port: u16,
+        cache: Arc<Mutex<FingerprintCache>>,
+        cache_path: &str,
+        client_identity: Option<ClientIdentity>,
+    ) -> Result<Self, NVDARemoteError> {
+        let remote = Self::new(
+            host,
+            key,
+            connection_type,
+            port,
+            CertVerifier::Fingerprint(cache.clone()),
+            client_identity,
+        )
+        .await?;
+
+        // Clone the cache out and drop the lock before awaiting the write so a
+        // concurrent handshake on another host sharing this `Arc<Mutex<_>>`
+        // never blocks on `verify_server_cert`'s `cache.lock()` while this
+        // guard is held suspended mid-I/O.
+        let snapshot = cache.lock().unwrap().clone();
+        snapshot.save_to_file(cache_path).await?;
+
+        Ok(remote)
+    }
+
+    pub async fn join(&mut self) -> Result<(), NVDARemoteError> {
+        self.send(json!({"type": "protocol_version", "version": 2})).await?;
+        self.send(json!({"type": "join", "channel": self.channel, "connection_type": self.connection_type})).await
+    }
+
+    /// Sends a key press or release. `pressed_keys` tracks every key this
+    /// connection has told the remote end is still down, so [`Self::release_all_keys`]
+    /// can clean up if the session ends mid-keypress.
+    pub async fn press_key(
+        &mut self,
+        vk_code: i32,
+        scan_code: i32,
+        extended: bool,
+        pressed: bool,
+    ) -> Result<(), NVDARemoteError> {
+        self.send(json!({
+            "type": "key",
+            "vk_code": vk_code,
+            "scan_code": scan_code,
+            "extended": extended,
+            "pressed": pressed,
+        }))
+        .await?;
+
+        if pressed {
+            self.pressed_keys.insert((vk_code, scan_code, extended));
+        } else {
+            self.pressed_keys.remove(&(vk_code, scan_code, extended));
+        }
+
+        Ok(())
+    }
+
+    /// Releases every key this connection believes is still held down.
+    /// Call this before disconnecting so a dropped socket doesn't leave the
+    /// remote NVDA with "stuck" keys.
+    pub async fn release_all_keys(&mut self) -> Result<(), NVDARemoteError> {
+        // Snapshot instead of draining up front: if a send fails partway
+        // through, the keys not yet released must still be in `pressed_keys`
+        // so a later retry (e.g. the next successful reconnect) can still
+        // release them, instead of forgetting them the moment this call fails.
+        let keys: Vec<(i32, i32, bool)> = self.pressed_keys.iter().copied().collect();
+        for (vk_code, scan_code, extended) in keys {
+            self.send(json!({
+                "type": "key",
+                "vk_code": vk_code,
+                "scan_code": scan_code,
+                "extended": extended,
+                "pressed": false,
+            }))
+            .await?;
+            self.pressed_keys.remove(&(vk_code, scan_code, extended));
+        }
+        Ok(())
+    }
+
+    pub async fn send_clipboard_text(&mut self, text: &str) -> Result<(), NVDARemoteError> {
+        self.send(json!({"type": "set_clipboard_text", "text": text}))
+            .await
+    }
+
+    pub async fn send_sas(&mut self) -> Result<(), NVDARemoteError> {
+        self.send(json!({"type": "send_SAS"})).await
+    }
+
+    pub async fn cancel_speech(&mut self) -> Result<(), NVDARemoteError> {
+        self.send(json!({"type": "cancel"})).await
+    }
+
+    pub async fn speak(&mut self, sequence: Vec<SpeechSequence>) -> Result<(), NVDARemoteError> {
+        let sequence: Vec<serde_json::Value> = sequence.iter().map(SpeechSequence::to_value).collect();
+        self.send(json!({"type": "speak", "sequence": sequence})).await
     }
 
-    pub async fn send(&mut self, message: serde_json::Value) {
+    pub async fn send(&mut self, message: serde_json::Value) -> Result<(), NVDARemoteError> {
         let msg = message.to_string() + "\n";
-        self.soc.write_all(msg.as_bytes()).await.unwrap();
+        self.soc.write_all(msg.as_bytes()).await?;
+        Ok(())
     }
 
     pub async fn update(&mut self) -> Option<EventType> {
-        let mut buf = String::new();
-        let mut reader = BufReader::new(&mut self.soc);
-
-        if let Ok(bytes_read) = reader.read_line(&mut buf).await {
+        // Reads into `self.line_buf` rather than a local: if this future is
+        // cancelled (e.g. by the keepalive `timeout` in `session::run`)
+        // partway through a line, the bytes already pulled out of `soc`
+        // stay in `line_buf` for the next call to resume from instead of
+        // being dropped along with a local accumulator.
+        // `read_until` appends partial bytes to `line_buf` as it goes;
+        // `read_line` would not, since on cancellation it leaves its buffer
+        // empty.
+        if let Ok(bytes_read) = self.soc.read_until(b'\n', &mut self.line_buf).await {
             if bytes_read == 0 {
                 return None; // Disconnected
             }
 
-            let event = self.parse(buf).await;
+            let Ok(line) = String::from_utf8(std::mem::take(&mut self.line_buf)) else {
+                return None;
+            };
+            let event = self.parse(line).await;
             if let Some(callback) = &self.event_callback {
                 callback(event.clone());
             }
@@ -158,25 +325,7 @@ impl NVDARemote {
     }
 
     pub async fn parse(&mut self, data: String) -> EventType {
-        let j: serde_json::Value = serde_json::from_str(&data).unwrap();
-        match j["type"].as_str() {
-            Some("motd") => EventType::Motd(j["motd"].as_str().unwrap().to_string()),
-            Some("channel_joined") => {
-                self.uid = j["origin"].as_i64().unwrap() as i32;
-                EventType::ChannelJoined(self.uid)
-            }
-            Some("channel_left") => {
-                self.uid = 0;
-                EventType::ChannelLeft
-            }
-            Some("tone") => EventType::Beep(
-                j["hz"].as_i64().unwrap() as i32,
-                j["length"].as_i64().unwrap() as i32,
-                j["left"].as_i64().unwrap() as i32,
-                j["right"].as_i64().unwrap() as i32,
-            ),
-            _ => EventType::Invalid(data),
-        }
+        parse_event(data, &mut self.uid)
     }
 
     pub fn set_event_callback<F>(&mut self, callback: F)
@@ -187,6 +336,315 @@ impl NVDARemote {
     }
 }
 
-// This struct implements a dummy verifier that disables certificate validation.
+/// The actual parsing logic behind [`NVDARemote::parse`], pulled out into a
+/// free function so it can be unit-tested without a live connection. `uid`
+/// tracks the channel-local client id the same way `NVDARemote::uid` does:
+/// updated on `channel_joined`, reset on `channel_left`.
+fn parse_event(data: String, uid: &mut i32) -> EventType {
+    let Ok(j) = serde_json::from_str::<serde_json::Value>(&data) else {
+        return EventType::Invalid(data);
+    };
 
+    match j["type"].as_str() {
+        Some("motd") => match j["motd"].as_str() {
+            Some(motd) => EventType::Motd(motd.to_string()),
+            None => EventType::Invalid(data),
+        },
+        Some("channel_joined") => match j["origin"].as_i64() {
+            Some(origin) => {
+                *uid = origin as i32;
+                EventType::ChannelJoined(*uid)
+            }
+            None => EventType::Invalid(data),
+        },
+        Some("channel_left") => {
+            *uid = 0;
+            EventType::ChannelLeft
+        }
+        Some("tone") => match (
+            j["hz"].as_i64(),
+            j["length"].as_i64(),
+            j["left"].as_i64(),
+            j["right"].as_i64(),
+        ) {
+            (Some(hz), Some(length), Some(left), Some(right)) => {
+                EventType::Beep(hz as i32, length as i32, left as i32, right as i32)
+            }
+            _ => EventType::Invalid(data),
+        },
+        Some("key") => match (
+            j["vk_code"].as_i64(),
+            j["scan_code"].as_i64(),
+            j["extended"].as_bool(),
+            j["pressed"].as_bool(),
+        ) {
+            (Some(vk_code), Some(scan_code), Some(extended), Some(pressed)) => {
+                EventType::Key(vk_code as i32, scan_code as i32, extended, pressed)
+            }
+            _ => EventType::Invalid(data),
+        },
+        Some("speak") => match (j["sequence"].as_array(), j["origin"].as_i64()) {
+            (Some(sequence), Some(origin)) => {
+                let sequence = sequence.iter().map(SpeechSequence::from_value).collect();
+                EventType::Speak(sequence, origin as i32)
+            }
+            _ => EventType::Invalid(data),
+        },
+        Some("cancel") => EventType::CancelSpeech,
+        Some("pause_speech") => match j["pause"].as_bool() {
+            Some(pause) => EventType::PauseSpeech(pause),
+            None => EventType::Invalid(data),
+        },
+        Some("braille") => match (j["cells"].as_array(), j["origin"].as_i64()) {
+            (Some(cells), Some(origin)) => {
+                let cells = cells.iter().filter_map(|cell| cell.as_u64()).map(|cell| cell as u8).collect();
+                EventType::Braille(cells, origin as i32)
+            }
+            _ => EventType::Invalid(data),
+        },
+        Some("set_braille_info") => match (j["name"].as_str(), j["num_cells"].as_i64()) {
+            (Some(name), Some(num_cells)) => {
+                EventType::SetBrailleInfo(name.to_string(), num_cells as i32)
+            }
+            _ => EventType::Invalid(data),
+        },
+        Some("send_SAS") => EventType::SendSAS,
+        Some("set_clipboard_text") => match j["text"].as_str() {
+            Some(text) => EventType::Clipboard(text.to_string()),
+            None => EventType::Invalid(data),
+        },
+        Some("nvda_not_connected") => EventType::NvdaNotConnected,
+        Some("client_joined") => {
+            match (j["client"]["id"].as_i64(), j["client"]["connection_type"].as_str()) {
+                (Some(id), Some(connection_type)) => {
+                    EventType::ClientJoined(id as i32, connection_type.to_string())
+                }
+                _ => EventType::Invalid(data),
+            }
+        }
+        Some("client_left") => match j["client"]["id"].as_i64() {
+            Some(id) => EventType::ClientLeft(id as i32),
+            None => EventType::Invalid(data),
+        },
+        _ => EventType::Invalid(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> EventType {
+        let mut uid = 0;
+        parse_event(data.to_string(), &mut uid)
+    }
+
+    #[test]
+    fn motd_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "motd", "motd": "hello"}"#),
+            EventType::Motd(m) if m == "hello"
+        ));
+    }
+
+    #[test]
+    fn motd_missing_field() {
+        assert!(matches!(parse(r#"{"type": "motd"}"#), EventType::Invalid(_)));
+    }
+
+    #[test]
+    fn channel_joined_sets_uid() {
+        let mut uid = 0;
+        let event = parse_event(r#"{"type": "channel_joined", "origin": 7}"#.to_string(), &mut uid);
+        assert!(matches!(event, EventType::ChannelJoined(7)));
+        assert_eq!(uid, 7);
+    }
+
+    #[test]
+    fn channel_joined_missing_origin() {
+        assert!(matches!(
+            parse(r#"{"type": "channel_joined"}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn channel_left_resets_uid() {
+        let mut uid = 42;
+        let event = parse_event(r#"{"type": "channel_left"}"#.to_string(), &mut uid);
+        assert!(matches!(event, EventType::ChannelLeft));
+        assert_eq!(uid, 0);
+    }
+
+    #[test]
+    fn tone_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "tone", "hz": 500, "length": 50, "left": 1, "right": 1}"#),
+            EventType::Beep(500, 50, 1, 1)
+        ));
+    }
+
+    #[test]
+    fn tone_missing_field() {
+        assert!(matches!(
+            parse(r#"{"type": "tone", "hz": 500, "length": 50, "left": 1}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn key_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "key", "vk_code": 65, "scan_code": 30, "extended": false, "pressed": true}"#),
+            EventType::Key(65, 30, false, true)
+        ));
+    }
+
+    #[test]
+    fn key_wrong_type_for_field() {
+        assert!(matches!(
+            parse(r#"{"type": "key", "vk_code": 65, "scan_code": 30, "extended": "no", "pressed": true}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn speak_well_formed() {
+        let event = parse(r#"{"type": "speak", "sequence": ["hi"], "origin": 3}"#);
+        assert!(matches!(event, EventType::Speak(seq, 3) if seq.len() == 1));
+    }
+
+    #[test]
+    fn speak_missing_origin() {
+        assert!(matches!(
+            parse(r#"{"type": "speak", "sequence": ["hi"]}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn cancel_speech() {
+        assert!(matches!(parse(r#"{"type": "cancel"}"#), EventType::CancelSpeech));
+    }
+
+    #[test]
+    fn pause_speech_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "pause_speech", "pause": true}"#),
+            EventType::PauseSpeech(true)
+        ));
+    }
+
+    #[test]
+    fn pause_speech_missing_field() {
+        assert!(matches!(
+            parse(r#"{"type": "pause_speech"}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn braille_well_formed() {
+        let event = parse(r#"{"type": "braille", "cells": [1, 2, 3], "origin": 2}"#);
+        assert!(matches!(event, EventType::Braille(cells, 2) if cells == vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn braille_missing_origin() {
+        assert!(matches!(
+            parse(r#"{"type": "braille", "cells": [1, 2, 3]}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn set_braille_info_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "set_braille_info", "name": "focus 40", "num_cells": 40}"#),
+            EventType::SetBrailleInfo(name, 40) if name == "focus 40"
+        ));
+    }
+
+    #[test]
+    fn set_braille_info_missing_field() {
+        assert!(matches!(
+            parse(r#"{"type": "set_braille_info", "name": "focus 40"}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn send_sas() {
+        assert!(matches!(parse(r#"{"type": "send_SAS"}"#), EventType::SendSAS));
+    }
+
+    #[test]
+    fn set_clipboard_text_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "set_clipboard_text", "text": "copied"}"#),
+            EventType::Clipboard(t) if t == "copied"
+        ));
+    }
+
+    #[test]
+    fn set_clipboard_text_missing_field() {
+        assert!(matches!(
+            parse(r#"{"type": "set_clipboard_text"}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn nvda_not_connected() {
+        assert!(matches!(
+            parse(r#"{"type": "nvda_not_connected"}"#),
+            EventType::NvdaNotConnected
+        ));
+    }
+
+    #[test]
+    fn client_joined_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "client_joined", "client": {"id": 5, "connection_type": "slave"}}"#),
+            EventType::ClientJoined(5, ct) if ct == "slave"
+        ));
+    }
+
+    #[test]
+    fn client_joined_missing_connection_type() {
+        assert!(matches!(
+            parse(r#"{"type": "client_joined", "client": {"id": 5}}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn client_left_well_formed() {
+        assert!(matches!(
+            parse(r#"{"type": "client_left", "client": {"id": 9}}"#),
+            EventType::ClientLeft(9)
+        ));
+    }
+
+    #[test]
+    fn client_left_missing_id() {
+        assert!(matches!(
+            parse(r#"{"type": "client_left", "client": {}}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn unknown_type_is_invalid() {
+        assert!(matches!(
+            parse(r#"{"type": "something_unknown"}"#),
+            EventType::Invalid(_)
+        ));
+    }
+
+    #[test]
+    fn malformed_json_is_invalid() {
+        assert!(matches!(parse("not json"), EventType::Invalid(_)));
+    }
+}
 
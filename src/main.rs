@@ -1,17 +1,24 @@
-use nvda_remote::{ConnectionType, NVDARemote, NVDARemoteError};
+use nvda_remote::{CertVerifier, ConnectionType, NVDARemote, NVDARemoteError};
 
 #[tokio::main]
 async fn main() -> Result<(), NVDARemoteError> {
     // read key from environment variable
     let key = std::env::var("NVDAREMOTE_KEY").expect("NVDAREMOTE_KEY not set");
-    let mut nvda_remote = NVDARemote::new("nvdaremote.com", &key, ConnectionType::Slave, 6837).await?;
+    // CertVerifier::AllowAll matches this example's previous behavior; switch
+    // to CertVerifier::Fingerprint (see NVDARemote::new_with_cache) to pin
+    // the server's certificate instead.
+    let mut nvda_remote = NVDARemote::new(
+        "nvdaremote.com",
+        &key,
+        ConnectionType::Slave,
+        6837,
+        CertVerifier::AllowAll,
+        None,
+    )
+    .await?;
     
-    nvda_remote.join().await;
+    nvda_remote.join().await?;
+    nvda_remote.set_event_callback(|event| println!("Processed event: {:?}", event));
 
-    loop {
-        if let Some(event) = nvda_remote.update().await {
-            println!("Processed event: {:?}", event);
-        }
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-    }
+    nvda_remote.run(tokio::time::Duration::from_secs(30)).await
 }
@@ -0,0 +1,101 @@
+use std::cmp::min;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::time::Instant;
+
+use crate::{CertVerifier, ClientIdentity, ConnectionType, EventType, NVDARemote, NVDARemoteError};
+
+/// Everything [`NVDARemote::run`] needs to redial the server after the
+/// connection drops.
+#[derive(Clone)]
+pub struct ConnectOptions {
+    pub host: String,
+    pub key: String,
+    pub connection_type: ConnectionType,
+    pub port: u16,
+    pub verifier: CertVerifier,
+    pub client_identity: Option<ClientIdentity>,
+}
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+impl NVDARemote {
+    fn emit(&self, event: EventType) {
+        if let Some(callback) = &self.event_callback {
+            callback(event);
+        }
+    }
+
+    /// Runs the session until the process is stopped: delivers events
+    /// through the event callback, sends a `ping` keepalive every
+    /// `ping_interval` so idle NAT connections stay open, and transparently
+    /// reconnects (with exponential backoff) whenever the connection drops.
+    pub async fn run(&mut self, ping_interval: Duration) -> Result<(), NVDARemoteError> {
+        self.emit(EventType::Connected);
+        let mut last_ping = Instant::now();
+
+        loop {
+            match tokio::time::timeout(ping_interval, self.update()).await {
+                Ok(Some(_event)) => {}
+                Ok(None) => {
+                    self.emit(EventType::Disconnected);
+                    self.reconnect().await?;
+                    last_ping = Instant::now();
+                    continue;
+                }
+                Err(_elapsed) => {
+                    // Nothing arrived before the keepalive interval; ping below.
+                }
+            }
+
+            if last_ping.elapsed() >= ping_interval {
+                // A failed write means the connection is gone just as surely
+                // as a read returning EOF; treat it the same way instead of
+                // letting the error propagate out of an `unwrap()`.
+                if self.send(json!({"type": "ping"})).await.is_err() {
+                    self.emit(EventType::Disconnected);
+                    self.reconnect().await?;
+                }
+                last_ping = Instant::now();
+            }
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<(), NVDARemoteError> {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            self.emit(EventType::Reconnecting);
+            tokio::time::sleep(backoff).await;
+
+            self.emit(EventType::Connecting);
+            match NVDARemote::new(
+                &self.connect_options.host,
+                &self.connect_options.key,
+                self.connect_options.connection_type.clone(),
+                self.connect_options.port,
+                self.connect_options.verifier.clone(),
+                self.connect_options.client_identity.clone(),
+            )
+            .await
+            {
+                Ok(fresh) => {
+                    self.soc = fresh.soc;
+                    self.line_buf = fresh.line_buf;
+                    self.peer_certificate = fresh.peer_certificate;
+                    self.uid = 0;
+
+                    if self.join().await.is_ok() && self.release_all_keys().await.is_ok() {
+                        self.emit(EventType::Connected);
+                        return Ok(());
+                    }
+                }
+                Err(_) => {}
+            }
+
+            backoff = min(backoff * 2, MAX_BACKOFF);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use serde_json::Value;
+
+/// One item of a speech sequence carried by a `speak` event. NVDA Remote
+/// speech sequences mix plain text chunks with inline command objects (pitch
+/// changes, index markers, language changes, ...); commands are kept as raw
+/// JSON since their shape varies by command type.
+#[derive(Debug, Clone)]
+pub enum SpeechSequence {
+    Text(String),
+    Command(Value),
+}
+
+impl SpeechSequence {
+    pub(crate) fn from_value(value: &Value) -> Self {
+        match value.as_str() {
+            Some(text) => SpeechSequence::Text(text.to_string()),
+            None => SpeechSequence::Command(value.clone()),
+        }
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        match self {
+            SpeechSequence::Text(text) => Value::String(text.clone()),
+            SpeechSequence::Command(value) => value.clone(),
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::sync::{Arc, Mutex};
+
+use hickory_resolver::proto::rr::RecordType;
+use hickory_resolver::TokioAsyncResolver;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::fingerprint::{FingerprintCache, FingerprintVerifier};
+use crate::NVDARemoteError;
+
+/// Accepts any certificate without validation. This is the behavior
+/// `NVDARemote::new` used unconditionally before certificate-verification
+/// strategies existed; it stays available for callers who explicitly want
+/// it, but the name makes the insecurity hard to miss.
+#[derive(Debug)]
+pub struct AllowAllVerifier;
+
+impl ServerCertVerifier for AllowAllVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        vec![]
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+}
+
+/// A single DNS TLSA resource record, as used by DANE (RFC 6698) to bind a
+/// certificate to a hostname without a certificate authority.
+#[derive(Debug, Clone)]
+pub struct TlsaRecord {
+    pub cert_usage: u8,
+    pub selector: u8,
+    pub matching_type: u8,
+    pub data: Vec<u8>,
+}
+
+/// Looks up the TLSA records published at `_{port}._tcp.{host}`, e.g.
+/// `_6837._tcp.nvdaremote.com`.
+pub async fn lookup_tlsa_records(
+    resolver: &TokioAsyncResolver,
+    host: &str,
+    port: u16,
+) -> Result<Vec<TlsaRecord>, NVDARemoteError> {
+    let name = format!("_{}._tcp.{}", port, host);
+    let lookup = resolver
+        .lookup(name, RecordType::TLSA)
+        .await
+        .map_err(|e| NVDARemoteError::DnsError(e.to_string()))?;
+
+    Ok(lookup
+        .record_iter()
+        .filter_map(|record| record.data().and_then(|data| data.as_tlsa()))
+        .map(|tlsa| TlsaRecord {
+            cert_usage: u8::from(tlsa.cert_usage()),
+            selector: u8::from(tlsa.selector()),
+            matching_type: u8::from(tlsa.matching()),
+            data: tlsa.cert_data().to_vec(),
+        })
+        .collect())
+}
+
+/// DANE (RFC 6698) certificate verifier: a certificate is accepted if it
+/// matches at least one usage 3 (DANE-EE) TLSA record already looked up for
+/// this host/port. Records with any other certificate usage are ignored.
+#[derive(Debug)]
+pub struct DaneVerifier {
+    records: Vec<TlsaRecord>,
+}
+
+impl DaneVerifier {
+    pub fn new(records: Vec<TlsaRecord>) -> Self {
+        Self { records }
+    }
+
+    fn selector_bytes(selector: u8, cert_der: &[u8]) -> Option<Vec<u8>> {
+        match selector {
+            // Full certificate.
+            0 => Some(cert_der.to_vec()),
+            // SubjectPublicKeyInfo.
+            1 => {
+                let (_, cert) = x509_parser::parse_x509_certificate(cert_der).ok()?;
+                Some(cert.public_key().raw.to_vec())
+            }
+            _ => None,
+        }
+    }
+
+    fn matching_type_digest(matching_type: u8, data: &[u8]) -> Option<Vec<u8>> {
+        match matching_type {
+            0 => Some(data.to_vec()),
+            1 => Some(Sha256::digest(data).to_vec()),
+            2 => Some(Sha512::digest(data).to_vec()),
+            _ => None,
+        }
+    }
+}
+
+impl ServerCertVerifier for DaneVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let matches = self.records.iter().any(|record| {
+            // Usage 3 (DANE-EE) is the only mode that's meaningful without
+            // also validating a CA chain, which this verifier doesn't have.
+            if record.cert_usage != 3 {
+                return false;
+            }
+            let Some(selected) = Self::selector_bytes(record.selector, end_entity.as_ref()) else {
+                return false;
+            };
+            let Some(digest) = Self::matching_type_digest(record.matching_type, &selected) else {
+                return false;
+            };
+            digest == record.data
+        });
+
+        if matches {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(
+                "certificate did not match any TLSA record for this host".to_string(),
+            ))
+        }
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        crate::signature_verification_algorithms().supported_schemes()
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            crate::signature_verification_algorithms(),
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            crate::signature_verification_algorithms(),
+        )
+    }
+}
+
+/// The certificate-verification strategy a connection uses. Picked by the
+/// caller of [`crate::NVDARemote::new`] instead of being hard-coded, so
+/// "accept every certificate" is something a library user opts into rather
+/// than something that always happens.
+#[derive(Clone)]
+pub enum CertVerifier {
+    /// Accept any certificate. Matches this crate's original behavior; use
+    /// only when the transport is already trusted some other way.
+    AllowAll,
+    /// Trust-on-first-use pinning backed by a [`FingerprintCache`].
+    Fingerprint(Arc<Mutex<FingerprintCache>>),
+    /// DANE (RFC 6698): trust a certificate that matches one of the zone's
+    /// usage 3 (DANE-EE) TLSA records; other usages are ignored. Build the
+    /// record list with [`lookup_tlsa_records`] before connecting.
+    Dane(Vec<TlsaRecord>),
+}
+
+impl CertVerifier {
+    pub(crate) fn into_rustls_verifier(self, host: &str) -> Arc<dyn ServerCertVerifier> {
+        match self {
+            CertVerifier::AllowAll => Arc::new(AllowAllVerifier),
+            CertVerifier::Fingerprint(cache) => {
+                Arc::new(FingerprintVerifier::new(host.to_string(), cache))
+            }
+            CertVerifier::Dane(records) => Arc::new(DaneVerifier::new(records)),
+        }
+    }
+}